@@ -40,7 +40,6 @@ See the [render::entity] module for an example of rendering an example entity.
 
 use std::borrow::Borrow;
 use std::collections::HashMap;
-use std::sync::mpsc::{channel, Receiver, Sender};
 use std::sync::Arc;
 
 use glam::IVec3;
@@ -49,14 +48,20 @@ use mc::Scene;
 pub use minecraft_assets;
 use parking_lot::{Mutex, RwLock};
 pub use wgpu;
-use wgpu::{BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BufferDescriptor, Surface};
+use wgpu::{BindGroupLayout, Surface};
 use winit::dpi::PhysicalSize;
 use winit::window::Window;
 
 use crate::mc::resource::ResourceProvider;
 use crate::mc::MinecraftState;
 use crate::render::atlas::Atlas;
+use crate::render::compute::{AnimationStepper, SectionCuller};
+use crate::render::graph::RenderGraph;
 use crate::render::pipeline::{create_bind_group_layouts, BLOCK_ATLAS, ENTITY_ATLAS};
+use crate::render::uniform::{create_uniform_array_buffer, BufferKind, GpuUniformArray};
+use crate::util::{
+    update_channel, CpuWriteGpuReadBelt, Id, Identified, UpdateReceiver, UpdateSender,
+};
 
 pub mod mc;
 pub mod render;
@@ -75,20 +80,105 @@ pub struct Display {
     pub config: RwLock<wgpu::SurfaceConfiguration>,
 }
 
+#[cfg(target_arch = "wasm32")]
+impl Display {
+    /// Sets up wgpu against an existing `<canvas>` element rather than a
+    /// [winit::window::Window], since there's no native window handle to
+    /// create a `Surface` from in the browser. Requests the adapter/device
+    /// asynchronously, as `navigator.gpu.requestAdapter`/`requestDevice` are
+    /// promise-based in WebGPU.
+    pub async fn from_canvas(canvas: web_sys::HtmlCanvasElement) -> Self {
+        let size = (canvas.width(), canvas.height());
+
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends: wgpu::Backends::BROWSER_WEBGPU,
+            ..Default::default()
+        });
+
+        let surface = instance
+            .create_surface(wgpu::SurfaceTarget::Canvas(canvas))
+            .expect("failed to create a wgpu surface from the given canvas");
+
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                compatible_surface: Some(&surface),
+                ..Default::default()
+            })
+            .await
+            .expect("no compatible wgpu adapter available in this browser");
+
+        let (device, queue) = adapter
+            .request_device(&wgpu::DeviceDescriptor::default(), None)
+            .await
+            .expect("failed to request a wgpu device from the browser adapter");
+
+        let config = surface
+            .get_default_config(&adapter, size.0, size.1)
+            .expect("surface is incompatible with the requested adapter");
+        surface.configure(&device, &config);
+
+        Self {
+            instance,
+            adapter,
+            surface,
+            device,
+            queue,
+            config: RwLock::new(config),
+        }
+    }
+}
+
 /// Tuple of chunk positions and baked layers
 pub type ChunkUpdateData = (IVec3, Vec<BakedLayer>);
 
 /// The main wgpu-mc renderer struct
 /// Resources pertaining to Minecraft go in `MinecraftState`.
 ///
-/// `RenderGraph` is used in tandem with `World` to render scenes.
+/// [RenderGraph](render::graph::RenderGraph) is used in tandem with `World` to render scenes.
 pub struct WmRenderer {
     pub gpu: Display,
     pub bind_group_layouts: Arc<HashMap<String, BindGroupLayout>>,
     pub mc: MinecraftState,
-    pub chunk_update_queue: (Sender<ChunkUpdateData>, Mutex<Receiver<ChunkUpdateData>>),
+    pub chunk_update_queue: (
+        UpdateSender<ChunkUpdateData>,
+        Mutex<UpdateReceiver<ChunkUpdateData>>,
+    ),
+    /// Pooled staging buffers used to batch chunk vertex/index uploads into
+    /// a single set of `copy_buffer_to_buffer` commands per frame. See
+    /// [util::staging_belt].
+    chunk_staging_belt: Mutex<CpuWriteGpuReadBelt>,
+    /// Bind groups keyed by the [Id] of the buffer/texture they were built
+    /// from, so a reuploaded resource invalidates its dependents by id
+    /// comparison instead of an ad-hoc `Option`/`is_none()` check. Each
+    /// entry is the same `Arc` handed to the corresponding `mc` field (e.g.
+    /// `mc.animated_block_bind_group`), so both stay in sync without a copy.
+    bind_group_cache: Mutex<HashMap<u64, Arc<wgpu::BindGroup>>>,
+    animated_block_buffer_id: Mutex<Option<Id<Arc<wgpu::Buffer>>>>,
+    /// The [GpuUniformArray] backing the animated block SSBO, kept alive
+    /// across uploads (rather than just handing its buffer/bind group off to
+    /// `mc` and dropping the rest) so every reupload in
+    /// [WmRenderer::upload_animated_block_buffer] goes through
+    /// [GpuUniformArray::write] and its capacity assert, instead of an
+    /// unchecked `queue.write_buffer` call.
+    animated_block_array: Mutex<Option<GpuUniformArray<f32>>>,
+    /// GPU animated-texture stepping, when the adapter supports compute
+    /// shaders. `None` on headless/low-feature backends, in which case
+    /// callers should keep driving [WmRenderer::upload_animated_block_buffer]
+    /// from the CPU instead.
+    pub animation_stepper: Option<AnimationStepper>,
+    /// GPU frustum culling into an indirect draw-args buffer, when the
+    /// adapter supports compute shaders; sections hidden behind other
+    /// geometry are not occlusion-culled. `None` on headless/low-feature
+    /// backends, in which case callers should keep culling sections on the
+    /// CPU with [Frustum] before building their draw list.
+    pub section_culler: Option<SectionCuller>,
 }
 
+/// Initial number of animated blocks [WmRenderer::animation_stepper] has
+/// room for. Not a hard cap — [AnimationStepper::write_descriptors] grows
+/// the backing buffer in place once the animation count exceeds it.
+const DEFAULT_ANIMATION_CAPACITY: usize = 256;
+
 #[derive(Copy, Clone)]
 pub struct WindowSize {
     pub width: u32,
@@ -102,12 +192,28 @@ pub trait HasWindowSize {
 impl WmRenderer {
     pub fn new(display: Display, resource_provider: Arc<dyn ResourceProvider>) -> WmRenderer {
         let mc = MinecraftState::new(&display, resource_provider);
-        let (sender, receiver) = channel();
+        let (sender, receiver) = update_channel();
+        let bind_group_layouts = Arc::new(create_bind_group_layouts(&display.device));
+
+        let animation_stepper = AnimationStepper::new(
+            &display.device,
+            &display.adapter,
+            &bind_group_layouts,
+            DEFAULT_ANIMATION_CAPACITY,
+        );
+        let section_culler = SectionCuller::new(&display.device, &display.adapter);
+
         Self {
-            bind_group_layouts: Arc::new(create_bind_group_layouts(&display.device)),
+            bind_group_layouts,
             gpu: display,
             mc,
             chunk_update_queue: (sender, Mutex::new(receiver)),
+            chunk_staging_belt: Mutex::new(CpuWriteGpuReadBelt::new(1024 * 1024)),
+            bind_group_cache: Mutex::new(HashMap::new()),
+            animated_block_buffer_id: Mutex::new(None),
+            animated_block_array: Mutex::new(None),
+            animation_stepper,
+            section_culler,
         }
     }
 
@@ -121,68 +227,124 @@ impl WmRenderer {
     }
 
     pub fn upload_animated_block_buffer(&self, data: Vec<f32>) {
-        let d = data.as_slice();
-
-        let buf = self.mc.animated_block_buffer.borrow().load_full();
-
-        if buf.is_none() {
-            let animated_block_buffer = self.gpu.device.create_buffer(&BufferDescriptor {
-                label: None,
-                size: (d.len() * 8) as wgpu::BufferAddress,
-                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-                mapped_at_creation: false,
-            });
-            let animated_block_bind_group =
-                self.gpu.device.create_bind_group(&BindGroupDescriptor {
-                    label: None,
-                    layout: self.bind_group_layouts.get("ssbo").unwrap(),
-                    entries: &[BindGroupEntry {
-                        binding: 0,
-                        resource: wgpu::BindingResource::Buffer(
-                            animated_block_buffer.as_entire_buffer_binding(),
-                        ),
-                    }],
-                });
-
-            self.mc
-                .animated_block_buffer
-                .store(Arc::new(Some(animated_block_buffer)));
-            self.mc
-                .animated_block_bind_group
-                .store(Arc::new(Some(animated_block_bind_group)));
+        let needs_resize = match self.animated_block_array.lock().as_ref() {
+            Some(array) => data.len() > array.capacity,
+            None => true,
+        };
+
+        if needs_resize {
+            let array = create_uniform_array_buffer::<f32>(
+                &self.gpu.device,
+                &self.bind_group_layouts,
+                "ssbo",
+                BufferKind::Storage,
+                data.len(),
+            );
+
+            let buffer = Identified::new(array.buffer.clone());
+            let id = buffer.id();
+            let bind_group = array.bind_group.clone();
+
+            let mut bind_group_cache = self.bind_group_cache.lock();
+            if let Some(old_id) = self.animated_block_buffer_id.lock().replace(id) {
+                // The old entry is never looked up again once its id has
+                // been replaced, so leaving it cached would just pin its
+                // bind group (and the GPU resources it keeps alive) forever.
+                bind_group_cache.remove(&old_id.value());
+            }
+            bind_group_cache.insert(id.value(), bind_group.clone());
+            drop(bind_group_cache);
+
+            self.mc.animated_block_buffer.store(Some(buffer.into_inner()));
+            self.mc.animated_block_bind_group.store(Some(bind_group));
+
+            *self.animated_block_array.lock() = Some(array);
         }
 
-        self.gpu.queue.write_buffer(
-            (**self.mc.animated_block_buffer.load()).as_ref().unwrap(),
-            0,
-            bytemuck::cast_slice(d),
-        );
+        self.animated_block_array
+            .lock()
+            .as_ref()
+            .expect("just created above if missing")
+            .write(&self.gpu.queue, &data);
     }
 
+    /// Returns the animated block SSBO's bind group, if
+    /// [WmRenderer::upload_animated_block_buffer] has created one. This is
+    /// the same value as `mc.animated_block_bind_group`, looked up by the
+    /// buffer's [Id] rather than an `Option` flag, so a future reupload that
+    /// assigns the buffer a new id naturally invalidates the cached entry
+    /// instead of returning a bind group for a buffer that no longer exists.
+    pub fn animated_block_bind_group(&self) -> Option<Arc<wgpu::BindGroup>> {
+        let id = (*self.animated_block_buffer_id.lock())?;
+        self.bind_group_cache.lock().get(&id.value()).cloned()
+    }
+
+    /// Drains the chunk update queue, writing the baked vertex/index data for
+    /// each updated section into the shared staging belt instead of going
+    /// through `queue.write_buffer` directly. Call [WmRenderer::before_submit]
+    /// before submitting the frame's `CommandEncoder` to flush these writes.
     pub fn submit_chunk_updates(&self, scene: &Scene) {
         let receiver = self.chunk_update_queue.1.lock();
         let updates = receiver.try_iter();
+        let mut belt = self.chunk_staging_belt.lock();
 
         updates.for_each(|(pos, layers)| {
             let mut storage = scene.section_storage.write();
             let section = storage.replace(pos, &layers);
             for (i, ranges) in section.layers.iter().enumerate() {
                 if let Some(ranges) = ranges {
-                    self.gpu.queue.write_buffer(
-                        &scene.chunk_buffer.buffer,
+                    let vertices = &layers[i].vertices;
+                    belt.allocate(
+                        &self.gpu.device,
                         ranges.vertex_range.start as u64 * 4,
-                        &layers[i].vertices,
-                    );
-                    self.gpu.queue.write_buffer(
-                        &scene.chunk_buffer.buffer,
+                        vertices.len() as u64,
+                    )
+                    .copy_from_slice(vertices);
+
+                    let indices = &layers[i].indices;
+                    belt.allocate(
+                        &self.gpu.device,
                         ranges.index_range.start as u64 * 4,
-                        &layers[i].indices,
-                    );
+                        indices.len() as u64,
+                    )
+                    .copy_from_slice(indices);
                 }
             }
         });
     }
 
+    /// Unmaps the staging belt's active chunks and records this frame's
+    /// batched chunk uploads into `encoder`. Must be called once per frame,
+    /// after [WmRenderer::submit_chunk_updates] and before `queue.submit`.
+    pub fn before_submit(&self, encoder: &mut wgpu::CommandEncoder, scene: &Scene) {
+        self.chunk_staging_belt
+            .lock()
+            .before_submit(encoder, &scene.chunk_buffer.buffer);
+    }
+
+    /// Returns this frame's retired staging chunks to the pool once the GPU
+    /// is done reading from them. Call after `queue.submit`.
+    pub fn recall_staging_belt(&self) {
+        self.chunk_staging_belt.lock().recall();
+    }
+
+    /// Runs one frame of `graph`: topologically sorts its nodes, allocates
+    /// their transient attachments, and submits the resulting
+    /// `CommandEncoder`. Built-in terrain/entity rendering and any
+    /// downstream post-processing nodes are driven identically through this
+    /// entry point rather than the core loop touching them directly.
+    ///
+    /// Also flushes the chunk staging belt (see [WmRenderer::before_submit]
+    /// and [WmRenderer::recall_staging_belt]) into the same encoder, so
+    /// callers that adopt `render` as their per-frame driver still get
+    /// [WmRenderer::submit_chunk_updates]'s batched chunk uploads applied.
+    pub fn render(&self, graph: &RenderGraph, scene: &Scene) {
+        let mut encoder = graph.execute(&self.gpu.device, scene);
+        self.before_submit(&mut encoder, scene);
+        self.gpu.queue.submit(Some(encoder.finish()));
+        self.recall_staging_belt();
+    }
+
     pub fn get_backend_description(&self) -> String {
         format!(
             "wgpu {} ({})",
@@ -0,0 +1,459 @@
+//! Compute-shader passes that move per-frame CPU work onto the GPU: stepping
+//! animated block texture frames, and frustum-culling chunk sections into an
+//! indirect draw-args buffer.
+//!
+//! Both passes are optional — [AnimationStepper::new] and
+//! [SectionCuller::new] return `None` when the adapter doesn't report
+//! [wgpu::DownlevelFlags::COMPUTE_SHADERS], so headless/low-feature backends
+//! (e.g. WebGL) can keep using the CPU path
+//! ([crate::WmRenderer::upload_animated_block_buffer] and the `treeculler`
+//! frustum check) unchanged. [AnimationStepper::new] additionally requires
+//! [wgpu::Features::PUSH_CONSTANTS], which WebGPU backends (including the
+//! browser target from [crate::Display::from_canvas]) don't expose, so it
+//! falls back the same way on those.
+
+use std::collections::HashMap;
+
+use bytemuck::{Pod, Zeroable};
+use parking_lot::Mutex;
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingType, Buffer, BufferBindingType, ComputePipeline,
+    ComputePipelineDescriptor, Device, DownlevelFlags, PipelineLayoutDescriptor, ShaderModule,
+    ShaderModuleDescriptor, ShaderSource, ShaderStages,
+};
+
+use crate::render::uniform::{create_uniform_array_buffer, BufferKind, GpuUniformArray};
+
+fn supports_compute(adapter: &wgpu::Adapter) -> bool {
+    adapter
+        .get_downlevel_capabilities()
+        .flags
+        .contains(DownlevelFlags::COMPUTE_SHADERS)
+}
+
+/// Whether `adapter` can back a pipeline layout with
+/// [wgpu::PushConstantRange]s. WebGPU adapters (the browser target added by
+/// [crate::Display::from_canvas]) report compute shader support but not
+/// this, so [AnimationStepper::new] must check both.
+fn supports_push_constants(adapter: &wgpu::Adapter) -> bool {
+    adapter.features().contains(wgpu::Features::PUSH_CONSTANTS)
+}
+
+/// Per-animation metadata driving how many frames an animation has and how
+/// often it advances. One entry per animated block, in the same order as the
+/// animated block SSBO. `interpolate` is stored but not read by
+/// [ANIMATION_SHADER], which always writes whole frame indices and does not
+/// blend between frames.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct AnimationDescriptor {
+    pub frame_count: u32,
+    pub tick_interval: u32,
+    pub interpolate: u32,
+    pub _padding: u32,
+}
+
+const ANIMATION_SHADER: &str = r#"
+struct AnimationDescriptor {
+    frame_count: u32,
+    tick_interval: u32,
+    interpolate: u32,
+    _padding: u32,
+}
+
+@group(0) @binding(0) var<storage, read> descriptors: array<AnimationDescriptor>;
+@group(0) @binding(1) var<storage, read_write> animated_blocks: array<f32>;
+
+struct PushConstants {
+    ticks_elapsed: u32,
+    animation_count: u32,
+}
+var<push_constant> pc: PushConstants;
+
+@compute @workgroup_size(64)
+fn step_animations(@builtin(global_invocation_id) id: vec3<u32>) {
+    let index = id.x;
+    if (index >= pc.animation_count) {
+        return;
+    }
+
+    let descriptor = descriptors[index];
+    let ticks_per_frame = max(descriptor.tick_interval, 1u);
+    let frame = (pc.ticks_elapsed / ticks_per_frame) % max(descriptor.frame_count, 1u);
+    animated_blocks[index] = f32(frame);
+}
+"#;
+
+/// Mirrors `PushConstants` in [ANIMATION_SHADER].
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct AnimationPushConstants {
+    ticks_elapsed: u32,
+    animation_count: u32,
+}
+
+/// Advances animated block texture frames entirely on the GPU from a small
+/// per-animation [AnimationDescriptor] buffer, eliminating the per-frame CPU
+/// upload [crate::WmRenderer::upload_animated_block_buffer] otherwise does.
+pub struct AnimationStepper {
+    pipeline: ComputePipeline,
+    bind_group_layout: BindGroupLayout,
+    /// Mutex-guarded so [AnimationStepper::write_descriptors] can recreate it
+    /// with a larger capacity in place when the animation count outgrows the
+    /// buffer it was created (or last resized) with, instead of panicking.
+    descriptors: Mutex<GpuUniformArray<AnimationDescriptor>>,
+}
+
+impl AnimationStepper {
+    /// Returns `None` if the adapter doesn't support compute shaders or push
+    /// constants (used here for `ticks_elapsed`); the caller should fall
+    /// back to [crate::WmRenderer::upload_animated_block_buffer].
+    pub fn new(
+        device: &Device,
+        adapter: &wgpu::Adapter,
+        bind_group_layouts: &HashMap<String, BindGroupLayout>,
+        animation_capacity: usize,
+    ) -> Option<Self> {
+        if !supports_compute(adapter) || !supports_push_constants(adapter) {
+            return None;
+        }
+
+        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("wgpu-mc animation stepper bind group layout"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: false },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("wgpu-mc animation stepper shader"),
+            source: ShaderSource::Wgsl(ANIMATION_SHADER.into()),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("wgpu-mc animation stepper pipeline layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[wgpu::PushConstantRange {
+                stages: ShaderStages::COMPUTE,
+                range: 0..std::mem::size_of::<AnimationPushConstants>() as u32,
+            }],
+        });
+
+        let pipeline = device.create_compute_pipeline(&ComputePipelineDescriptor {
+            label: Some("wgpu-mc animation stepper pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: "step_animations",
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        let descriptors = create_uniform_array_buffer::<AnimationDescriptor>(
+            device,
+            bind_group_layouts,
+            "ssbo",
+            BufferKind::Storage,
+            animation_capacity,
+        );
+
+        Some(Self {
+            pipeline,
+            bind_group_layout,
+            descriptors: Mutex::new(descriptors),
+        })
+    }
+
+    /// Uploads this frame's [AnimationDescriptor]s; call once whenever an
+    /// animation is added, removed, or changes its frame count/interval.
+    ///
+    /// Recreates the backing SSBO with room for `descriptors.len()` entries
+    /// if it has outgrown the capacity passed to [AnimationStepper::new] (or
+    /// a previous resize), rather than hitting [GpuUniformArray::write]'s
+    /// capacity assert.
+    pub fn write_descriptors(
+        &self,
+        device: &Device,
+        bind_group_layouts: &HashMap<String, BindGroupLayout>,
+        queue: &wgpu::Queue,
+        descriptors: &[AnimationDescriptor],
+    ) {
+        let mut array = self.descriptors.lock();
+        if descriptors.len() > array.capacity {
+            *array = create_uniform_array_buffer::<AnimationDescriptor>(
+                device,
+                bind_group_layouts,
+                "ssbo",
+                BufferKind::Storage,
+                descriptors.len(),
+            );
+        }
+        array.write(queue, descriptors);
+    }
+
+    /// Dispatches one invocation per animation, writing the current frame
+    /// index of each into `animated_block_buffer` - the same SSBO
+    /// [crate::WmRenderer::upload_animated_block_buffer] otherwise fills
+    /// from the CPU.
+    pub fn step(
+        &self,
+        device: &Device,
+        encoder: &mut wgpu::CommandEncoder,
+        animated_block_buffer: &Buffer,
+        animation_count: u32,
+        ticks_elapsed: u32,
+    ) {
+        let descriptors = self.descriptors.lock();
+        let bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("wgpu-mc animation stepper bind group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: descriptors.buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: animated_block_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("wgpu-mc animation stepper pass"),
+            timestamp_writes: None,
+        });
+        let push_constants = AnimationPushConstants {
+            ticks_elapsed,
+            animation_count,
+        };
+
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.set_push_constants(0, bytemuck::bytes_of(&push_constants));
+        pass.dispatch_workgroups(animation_count.div_ceil(64), 1, 1);
+    }
+}
+
+/// A section's axis-aligned bounding box, in world space.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct SectionBounds {
+    pub min: [f32; 3],
+    pub _pad0: f32,
+    pub max: [f32; 3],
+    pub _pad1: f32,
+}
+
+/// The camera frustum's six clip planes, `ax + by + cz + d = 0` each.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct FrustumPlanes {
+    pub planes: [[f32; 4]; 6],
+}
+
+/// Mirrors [wgpu::util::DrawIndexedIndirectArgs]'s GPU layout, one entry per
+/// section, written by the culling pass and consumed by
+/// `RenderPass::draw_indexed_indirect`.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct DrawIndexedIndirectArgs {
+    pub index_count: u32,
+    pub instance_count: u32,
+    pub first_index: u32,
+    pub base_vertex: i32,
+    pub first_instance: u32,
+}
+
+const CULLING_SHADER: &str = r#"
+struct SectionBounds {
+    min: vec3<f32>,
+    _pad0: f32,
+    max: vec3<f32>,
+    _pad1: f32,
+}
+
+struct Frustum {
+    planes: array<vec4<f32>, 6>,
+}
+
+struct DrawIndexedIndirectArgs {
+    index_count: u32,
+    instance_count: u32,
+    first_index: u32,
+    base_vertex: i32,
+    first_instance: u32,
+}
+
+@group(0) @binding(0) var<storage, read> bounds: array<SectionBounds>;
+@group(0) @binding(1) var<uniform> frustum: Frustum;
+@group(0) @binding(2) var<storage, read_write> draw_args: array<DrawIndexedIndirectArgs>;
+
+fn aabb_in_frustum(b: SectionBounds) -> bool {
+    for (var i = 0u; i < 6u; i = i + 1u) {
+        let plane = frustum.planes[i];
+        let positive = vec3<f32>(
+            select(b.min.x, b.max.x, plane.x >= 0.0),
+            select(b.min.y, b.max.y, plane.y >= 0.0),
+            select(b.min.z, b.max.z, plane.z >= 0.0),
+        );
+        if (dot(plane.xyz, positive) + plane.w < 0.0) {
+            return false;
+        }
+    }
+    return true;
+}
+
+@compute @workgroup_size(64)
+fn cull_sections(@builtin(global_invocation_id) id: vec3<u32>) {
+    let index = id.x;
+    if (index >= arrayLength(&bounds)) {
+        return;
+    }
+
+    // instance_count is set to 0/1 to toggle visibility; index_count/
+    // first_index/base_vertex are filled in by the caller ahead of time from
+    // the section's known mesh range, since the culling pass only decides
+    // whether a section draws at all this frame.
+    draw_args[index].instance_count = select(0u, 1u, aabb_in_frustum(bounds[index]));
+}
+"#;
+
+/// Reads per-section bounding boxes and the camera frustum, and toggles
+/// `instance_count` in a per-section [DrawIndexedIndirectArgs] entry so
+/// invisible sections are skipped by `draw_indexed_indirect` without a CPU
+/// round trip through `treeculler::Frustum`.
+pub struct SectionCuller {
+    pipeline: ComputePipeline,
+    bind_group_layout: BindGroupLayout,
+}
+
+impl SectionCuller {
+    /// Returns `None` if the adapter doesn't support compute shaders; the
+    /// caller should fall back to culling on the CPU with
+    /// [crate::Frustum] before building its draw list.
+    pub fn new(device: &Device, adapter: &wgpu::Adapter) -> Option<Self> {
+        if !supports_compute(adapter) {
+            return None;
+        }
+
+        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("wgpu-mc section culler bind group layout"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: false },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("wgpu-mc section culler shader"),
+            source: ShaderSource::Wgsl(CULLING_SHADER.into()),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("wgpu-mc section culler pipeline layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_compute_pipeline(&ComputePipelineDescriptor {
+            label: Some("wgpu-mc section culler pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: "cull_sections",
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        Some(Self {
+            pipeline,
+            bind_group_layout,
+        })
+    }
+
+    /// Dispatches one invocation per section, toggling `instance_count` in
+    /// `indirect_args` for each section against `frustum`.
+    pub fn cull(
+        &self,
+        device: &Device,
+        encoder: &mut wgpu::CommandEncoder,
+        section_bounds: &Buffer,
+        section_count: u32,
+        frustum: &Buffer,
+        indirect_args: &Buffer,
+    ) {
+        let bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("wgpu-mc section culler bind group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: section_bounds.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: frustum.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: indirect_args.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("wgpu-mc section culling pass"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.dispatch_workgroups(section_count.div_ceil(64), 1, 1);
+    }
+}
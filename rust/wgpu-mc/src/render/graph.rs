@@ -0,0 +1,367 @@
+//! A declarative, node-based render graph layered over the pipelines that
+//! otherwise drive rendering directly against a `CommandEncoder`.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+use wgpu::{Buffer, BufferAddress, BufferDescriptor, BufferUsages, CommandEncoder, Device};
+use wgpu::{Texture, TextureDescriptor, TextureFormat, TextureUsages};
+
+use crate::mc::Scene;
+
+/// Describes a texture or buffer a [RenderNode] produces.
+#[derive(Clone)]
+pub enum GraphResource {
+    Texture {
+        width: u32,
+        height: u32,
+        format: TextureFormat,
+        usage: TextureUsages,
+    },
+    Buffer {
+        size: BufferAddress,
+        usage: BufferUsages,
+    },
+}
+
+/// A resolved transient resource, handed to nodes that declared it as an
+/// input or output.
+pub enum GraphResourceBinding {
+    Texture(Arc<Texture>),
+    Buffer(Arc<Buffer>),
+}
+
+/// The resolved textures/buffers for one graph execution, keyed by the name
+/// under which they were declared as a node output.
+#[derive(Default)]
+pub struct GraphContext {
+    resources: HashMap<String, GraphResourceBinding>,
+}
+
+impl GraphContext {
+    pub fn texture(&self, name: &str) -> Option<&Texture> {
+        match self.resources.get(name) {
+            Some(GraphResourceBinding::Texture(texture)) => Some(texture.as_ref()),
+            _ => None,
+        }
+    }
+
+    pub fn buffer(&self, name: &str) -> Option<&Buffer> {
+        match self.resources.get(name) {
+            Some(GraphResourceBinding::Buffer(buffer)) => Some(buffer.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+type NodeExecute = Box<dyn Fn(&mut CommandEncoder, &GraphContext, &Scene) + Send + Sync>;
+
+/// One step of the render graph: a pass (or compute dispatch) that consumes
+/// some named resources and produces others.
+pub struct RenderNode {
+    pub name: String,
+    /// Names of resources (produced by other nodes' `outputs`) this node
+    /// reads before it runs.
+    pub inputs: Vec<String>,
+    /// Resources this node produces, allocated by the graph before the node
+    /// runs.
+    pub outputs: Vec<(String, GraphResource)>,
+    /// Bind group layouts (by name, looked up in
+    /// [crate::WmRenderer::bind_group_layouts]) this node's pipeline(s) consume.
+    pub bind_group_layouts: Vec<String>,
+    execute: NodeExecute,
+}
+
+impl RenderNode {
+    pub fn new(
+        name: impl Into<String>,
+        execute: impl Fn(&mut CommandEncoder, &GraphContext, &Scene) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            bind_group_layouts: Vec::new(),
+            execute: Box::new(execute),
+        }
+    }
+
+    pub fn with_input(mut self, name: impl Into<String>) -> Self {
+        self.inputs.push(name.into());
+        self
+    }
+
+    pub fn with_output(mut self, name: impl Into<String>, resource: GraphResource) -> Self {
+        self.outputs.push((name.into(), resource));
+        self
+    }
+
+    pub fn with_bind_group_layout(mut self, name: impl Into<String>) -> Self {
+        self.bind_group_layouts.push(name.into());
+        self
+    }
+}
+
+/// A set of named [RenderNode]s and the data dependencies between them,
+/// derived from each node's declared inputs/outputs.
+#[derive(Default)]
+pub struct RenderGraph {
+    nodes: Vec<RenderNode>,
+}
+
+impl RenderGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_node(&mut self, node: RenderNode) {
+        self.nodes.push(node);
+    }
+
+    /// Returns the node indices in an order where every node runs after the
+    /// nodes that produce the resources it depends on (Kahn's algorithm).
+    /// Panics if the declared inputs/outputs describe a cycle.
+    fn topological_order(&self) -> Vec<usize> {
+        let producer_of = |resource: &str| {
+            self.nodes
+                .iter()
+                .position(|node| node.outputs.iter().any(|(name, _)| name == resource))
+        };
+
+        let mut dependents: Vec<HashSet<usize>> = vec![HashSet::new(); self.nodes.len()];
+        let mut remaining_deps: Vec<usize> = vec![0; self.nodes.len()];
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            for input in &node.inputs {
+                if let Some(producer) = producer_of(input) {
+                    if dependents[producer].insert(index) {
+                        remaining_deps[index] += 1;
+                    }
+                }
+            }
+        }
+
+        let mut ready: VecDeque<usize> = (0..self.nodes.len())
+            .filter(|&index| remaining_deps[index] == 0)
+            .collect();
+        let mut order = Vec::with_capacity(self.nodes.len());
+
+        while let Some(index) = ready.pop_front() {
+            order.push(index);
+            for &dependent in &dependents[index] {
+                remaining_deps[dependent] -= 1;
+                if remaining_deps[dependent] == 0 {
+                    ready.push_back(dependent);
+                }
+            }
+        }
+
+        assert_eq!(
+            order.len(),
+            self.nodes.len(),
+            "RenderGraph has a cycle in its node inputs/outputs"
+        );
+
+        order
+    }
+
+    /// Allocates every node's declared outputs, aliasing a transient
+    /// resource with an earlier one once the earlier one's last consumer has
+    /// already run.
+    fn allocate_resources(&self, device: &Device, order: &[usize]) -> GraphContext {
+        let mut last_consumer: HashMap<String, usize> = HashMap::new();
+        for (position, &index) in order.iter().enumerate() {
+            for input in &self.nodes[index].inputs {
+                last_consumer.insert(input.clone(), position);
+            }
+        }
+
+        let mut free_textures: Vec<(TextureFormat, u32, u32, TextureUsages, Arc<Texture>)> =
+            Vec::new();
+        let mut free_buffers: Vec<(BufferAddress, BufferUsages, Arc<Buffer>)> = Vec::new();
+        let mut context = GraphContext::default();
+
+        for (position, &index) in order.iter().enumerate() {
+            for (name, resource) in &self.nodes[index].outputs {
+                let binding = match *resource {
+                    GraphResource::Texture {
+                        width,
+                        height,
+                        format,
+                        usage,
+                    } => {
+                        // A free texture can only be reused if it was
+                        // allocated with every usage this output needs too —
+                        // matching dimensions alone isn't enough, since e.g.
+                        // a sampled-only attachment can't stand in for one
+                        // that also needs STORAGE_BINDING.
+                        let reused = free_textures
+                            .iter()
+                            .position(|(f, w, h, u, _)| {
+                                *f == format
+                                    && *w == width
+                                    && *h == height
+                                    && u.contains(usage)
+                            })
+                            .map(|slot_index| free_textures.swap_remove(slot_index).4);
+
+                        let texture = reused.unwrap_or_else(|| {
+                            Arc::new(device.create_texture(&TextureDescriptor {
+                                label: Some(name),
+                                size: wgpu::Extent3d {
+                                    width,
+                                    height,
+                                    depth_or_array_layers: 1,
+                                },
+                                mip_level_count: 1,
+                                sample_count: 1,
+                                dimension: wgpu::TextureDimension::D2,
+                                format,
+                                usage,
+                                view_formats: &[],
+                            }))
+                        });
+
+                        GraphResourceBinding::Texture(texture)
+                    }
+                    GraphResource::Buffer { size, usage } => {
+                        let reused = free_buffers
+                            .iter()
+                            .position(|(s, u, _)| *s >= size && u.contains(usage))
+                            .map(|slot_index| free_buffers.swap_remove(slot_index).2);
+
+                        let buffer = reused.unwrap_or_else(|| {
+                            Arc::new(device.create_buffer(&BufferDescriptor {
+                                label: Some(name),
+                                size,
+                                usage,
+                                mapped_at_creation: false,
+                            }))
+                        });
+
+                        GraphResourceBinding::Buffer(buffer)
+                    }
+                };
+
+                context.resources.insert(name.clone(), binding);
+            }
+
+            // Hand resources whose last consumer was this node to the free
+            // lists too, so a later node's output can alias the same
+            // underlying texture/buffer instead of allocating a fresh one.
+            // The `Arc` clone means the name stays resolvable in `context`
+            // for any node that runs later but was never actually going to
+            // read it again (it just wasn't declared as anyone's input).
+            for (name, &last) in last_consumer.iter() {
+                if last != position {
+                    continue;
+                }
+                match context.resources.get(name) {
+                    Some(GraphResourceBinding::Texture(texture)) => {
+                        free_textures.push((
+                            texture.format(),
+                            texture.width(),
+                            texture.height(),
+                            texture.usage(),
+                            texture.clone(),
+                        ));
+                    }
+                    Some(GraphResourceBinding::Buffer(buffer)) => {
+                        free_buffers.push((buffer.size(), buffer.usage(), buffer.clone()));
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        context
+    }
+
+    /// Topologically sorts the graph, allocates its transient attachments,
+    /// and drives every node's pass against a single [CommandEncoder].
+    pub fn execute(&self, device: &Device, scene: &Scene) -> CommandEncoder {
+        let order = self.topological_order();
+        let context = self.allocate_resources(device, &order);
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("wgpu-mc render graph encoder"),
+        });
+
+        for index in order {
+            (self.nodes[index].execute)(&mut encoder, &context, scene);
+        }
+
+        encoder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(name: &str) -> RenderNode {
+        RenderNode::new(name, |_, _, _| {})
+    }
+
+    #[test]
+    fn topological_order_runs_producers_before_consumers() {
+        let mut graph = RenderGraph::new();
+        graph.add_node(node("c").with_input("b_out"));
+        graph.add_node(
+            node("a").with_output(
+                "a_out",
+                GraphResource::Buffer {
+                    size: 0,
+                    usage: BufferUsages::empty(),
+                },
+            ),
+        );
+        graph.add_node(
+            node("b")
+                .with_input("a_out")
+                .with_output(
+                    "b_out",
+                    GraphResource::Buffer {
+                        size: 0,
+                        usage: BufferUsages::empty(),
+                    },
+                ),
+        );
+
+        let order = graph.topological_order();
+        let position = |name: &str| order.iter().position(|&i| graph.nodes[i].name == name);
+
+        assert!(position("a") < position("b"));
+        assert!(position("b") < position("c"));
+    }
+
+    #[test]
+    #[should_panic(expected = "cycle")]
+    fn topological_order_panics_on_cycle() {
+        let mut graph = RenderGraph::new();
+        graph.add_node(
+            node("a")
+                .with_input("b_out")
+                .with_output(
+                    "a_out",
+                    GraphResource::Buffer {
+                        size: 0,
+                        usage: BufferUsages::empty(),
+                    },
+                ),
+        );
+        graph.add_node(
+            node("b")
+                .with_input("a_out")
+                .with_output(
+                    "b_out",
+                    GraphResource::Buffer {
+                        size: 0,
+                        usage: BufferUsages::empty(),
+                    },
+                ),
+        );
+
+        graph.topological_order();
+    }
+}
@@ -0,0 +1,8 @@
+//! Rendering pipelines and the GPU resources they consume.
+//!
+//! See the [WmPipeline](super) trait for the extension point used to plug
+//! custom rendering logic into [crate::WmRenderer].
+
+pub mod compute;
+pub mod graph;
+pub mod uniform;
@@ -0,0 +1,107 @@
+//! Typed helpers around the "one buffer + one bind group" pattern used for
+//! uniforms and SSBOs, so call sites don't have to hand-roll
+//! [wgpu::BufferDescriptor] sizes or [wgpu::BindGroupDescriptor]s.
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, Buffer, BufferAddress,
+    BufferDescriptor, BufferUsages, Device, Queue,
+};
+
+/// The minimum uniform buffer offset alignment required by wgpu's downlevel
+/// limits. Storage buffers aren't required to respect this, but padding
+/// them to the same boundary keeps a single alignment rule for both kinds.
+const MIN_BUFFER_ALIGNMENT: BufferAddress = 256;
+
+/// Rounds `size` up to a multiple of [MIN_BUFFER_ALIGNMENT].
+fn padded_size(size: BufferAddress) -> BufferAddress {
+    ((size + MIN_BUFFER_ALIGNMENT - 1) / MIN_BUFFER_ALIGNMENT) * MIN_BUFFER_ALIGNMENT
+}
+
+/// Whether a [GpuUniformArray] is backed by a uniform buffer or a storage
+/// buffer. This only changes the buffer's [BufferUsages] and is otherwise
+/// transparent to callers.
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum BufferKind {
+    Uniform,
+    Storage,
+}
+
+impl BufferKind {
+    fn usages(self) -> BufferUsages {
+        match self {
+            BufferKind::Uniform => BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            BufferKind::Storage => BufferUsages::STORAGE | BufferUsages::COPY_DST,
+        }
+    }
+}
+
+/// A buffer holding a `Vec<T>`-shaped region of up to `capacity` elements,
+/// plus the bind group that exposes it at binding `0`. Used for SSBOs whose
+/// length varies from upload to upload, such as the animated block buffer.
+///
+/// `buffer`/`bind_group` are `Arc`-wrapped so a caller that needs to hand the
+/// same GPU resource to something else (e.g. keying a cache by identity, or
+/// storing it alongside this array) can clone the handle instead of reaching
+/// for `queue.write_buffer` directly and bypassing the capacity check below.
+pub struct GpuUniformArray<T: bytemuck::Pod> {
+    pub buffer: Arc<Buffer>,
+    pub bind_group: Arc<BindGroup>,
+    pub capacity: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<T: bytemuck::Pod> GpuUniformArray<T> {
+    /// Uploads `data`. Panics if `data` is longer than the capacity this
+    /// buffer was created with.
+    pub fn write(&self, queue: &Queue, data: &[T]) {
+        assert!(
+            data.len() <= self.capacity,
+            "GpuUniformArray<T> written with {} elements, capacity is {}",
+            data.len(),
+            self.capacity
+        );
+
+        queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(data));
+    }
+}
+
+/// Creates a [GpuUniformArray] with room for `capacity` elements of `T`,
+/// bound as binding `0` of the bind group layout named `layout_name`.
+pub fn create_uniform_array_buffer<T: bytemuck::Pod>(
+    device: &Device,
+    bind_group_layouts: &HashMap<String, BindGroupLayout>,
+    layout_name: &str,
+    kind: BufferKind,
+    capacity: usize,
+) -> GpuUniformArray<T> {
+    let size = padded_size((capacity * std::mem::size_of::<T>()) as BufferAddress);
+
+    let buffer = device.create_buffer(&BufferDescriptor {
+        label: Some("wgpu-mc uniform array buffer"),
+        size,
+        usage: kind.usages(),
+        mapped_at_creation: false,
+    });
+
+    let bind_group = device.create_bind_group(&BindGroupDescriptor {
+        label: Some("wgpu-mc uniform array bind group"),
+        layout: bind_group_layouts
+            .get(layout_name)
+            .unwrap_or_else(|| panic!("no bind group layout named \"{layout_name}\"")),
+        entries: &[BindGroupEntry {
+            binding: 0,
+            resource: buffer.as_entire_binding(),
+        }],
+    });
+
+    GpuUniformArray {
+        buffer: Arc::new(buffer),
+        bind_group: Arc::new(bind_group),
+        capacity,
+        _marker: PhantomData,
+    }
+}
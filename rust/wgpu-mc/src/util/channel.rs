@@ -0,0 +1,82 @@
+//! A channel used to move baked chunk mesh data from whatever bakes it to
+//! the render thread that uploads it, abstracted over the difference
+//! between native threads and Web Workers.
+//!
+//! On native targets this is a thin wrapper around `std::sync::mpsc`. On
+//! `wasm32-unknown-unknown` (built with `atomics`/`bulk-memory` enabled and
+//! this crate's `wasm` feature) there is no `std::thread`, so mesh baking
+//! instead runs on Web Workers sharing the module's linear memory; there we
+//! use `crossbeam_channel`, which works over that shared `SharedArrayBuffer`
+//! memory instead of `postMessage`, so `BakedLayer` vertex/index data moves
+//! between the worker and the render thread without a serialization step.
+
+#[cfg(not(target_arch = "wasm32"))]
+mod imp {
+    use std::sync::mpsc;
+
+    pub struct UpdateSender<T>(mpsc::Sender<T>);
+    pub struct UpdateReceiver<T>(mpsc::Receiver<T>);
+
+    impl<T> Clone for UpdateSender<T> {
+        fn clone(&self) -> Self {
+            Self(self.0.clone())
+        }
+    }
+
+    impl<T> UpdateSender<T> {
+        pub fn send(&self, value: T) -> Result<(), T> {
+            self.0.send(value).map_err(|mpsc::SendError(value)| value)
+        }
+    }
+
+    impl<T> UpdateReceiver<T> {
+        pub fn try_iter(&self) -> impl Iterator<Item = T> + '_ {
+            self.0.try_iter()
+        }
+    }
+
+    pub fn update_channel<T>() -> (UpdateSender<T>, UpdateReceiver<T>) {
+        let (sender, receiver) = mpsc::channel();
+        (UpdateSender(sender), UpdateReceiver(receiver))
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod imp {
+    use crossbeam_channel::{unbounded, Receiver, Sender, TryRecvError};
+
+    pub struct UpdateSender<T>(Sender<T>);
+    pub struct UpdateReceiver<T>(Receiver<T>);
+
+    impl<T> Clone for UpdateSender<T> {
+        fn clone(&self) -> Self {
+            Self(self.0.clone())
+        }
+    }
+
+    impl<T> UpdateSender<T> {
+        pub fn send(&self, value: T) -> Result<(), T> {
+            self.0.send(value).map_err(|err| err.into_inner())
+        }
+    }
+
+    impl<T> UpdateReceiver<T> {
+        pub fn try_iter(&self) -> impl Iterator<Item = T> + '_ {
+            std::iter::from_fn(move || match self.0.try_recv() {
+                Ok(value) => Some(value),
+                Err(TryRecvError::Empty) | Err(TryRecvError::Disconnected) => None,
+            })
+        }
+    }
+
+    /// `SharedArrayBuffer`-backed, since `crossbeam_channel` on
+    /// `wasm32-unknown-unknown` with the `atomics` target feature is built
+    /// on the same shared linear memory Web Workers use, rather than
+    /// `postMessage`.
+    pub fn update_channel<T>() -> (UpdateSender<T>, UpdateReceiver<T>) {
+        let (sender, receiver) = unbounded();
+        (UpdateSender(sender), UpdateReceiver(receiver))
+    }
+}
+
+pub use imp::{update_channel, UpdateReceiver, UpdateSender};
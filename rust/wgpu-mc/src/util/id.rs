@@ -0,0 +1,104 @@
+//! Process-unique identity for GPU resources, independent of any particular
+//! `wgpu` version's own id support (`wgpu::Buffer::global_id` and friends
+//! are being removed upstream).
+
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A cheap, `Copy` handle identifying a particular [Identified] value.
+pub struct Id<T> {
+    value: u64,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Id<T> {
+    /// The raw id value, suitable for use as a `HashMap` key.
+    pub fn value(self) -> u64 {
+        self.value
+    }
+}
+
+impl<T> Copy for Id<T> {}
+
+impl<T> Clone for Id<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> PartialEq for Id<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T> Eq for Id<T> {}
+
+impl<T> Hash for Id<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+impl<T> std::fmt::Debug for Id<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Id({})", self.value)
+    }
+}
+
+/// Wraps a `T`, assigning it a process-unique [Id] on construction.
+pub struct Identified<T> {
+    id: Id<T>,
+    value: T,
+}
+
+impl<T> Identified<T> {
+    /// Wraps `value`, assigning it the next id from a single global counter.
+    pub fn new(value: T) -> Self {
+        Self {
+            id: Id {
+                value: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+                _marker: PhantomData,
+            },
+            value,
+        }
+    }
+
+    pub fn id(&self) -> Id<T> {
+        self.id
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> std::ops::Deref for Identified<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> std::ops::DerefMut for Identified<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Identified;
+
+    #[test]
+    fn each_identified_value_gets_a_distinct_id() {
+        let a = Identified::new(1u32);
+        let b = Identified::new(2u32);
+
+        assert_ne!(a.id().value(), b.id().value());
+    }
+}
@@ -0,0 +1,10 @@
+//! Small, dependency-light helpers shared across the renderer that don't
+//! belong to any single [crate::mc] or [crate::render] submodule.
+
+pub mod channel;
+pub mod id;
+pub mod staging_belt;
+
+pub use channel::{update_channel, UpdateReceiver, UpdateSender};
+pub use id::{Id, Identified};
+pub use staging_belt::CpuWriteGpuReadBelt;
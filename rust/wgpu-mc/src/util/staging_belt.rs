@@ -0,0 +1,219 @@
+use wgpu::{
+    Buffer, BufferAddress, BufferDescriptor, BufferUsages, CommandEncoder, Device, MapMode,
+};
+
+/// Staging chunks default to 1 MiB unless a single allocation asks for more.
+const DEFAULT_CHUNK_SIZE: BufferAddress = 1024 * 1024;
+
+/// Rounds `size` up to a multiple of [wgpu::COPY_BUFFER_ALIGNMENT].
+fn align_copy_size(size: BufferAddress) -> BufferAddress {
+    let align = wgpu::COPY_BUFFER_ALIGNMENT;
+    (size + align - 1) / align * align
+}
+
+/// Whether a chunk of `chunk_size` bytes with `chunk_cursor` already
+/// consumed has room left for another `needed` bytes.
+fn has_remaining_capacity(
+    chunk_size: BufferAddress,
+    chunk_cursor: BufferAddress,
+    needed: BufferAddress,
+) -> bool {
+    chunk_size - chunk_cursor >= needed
+}
+
+struct Chunk {
+    buffer: Buffer,
+    size: BufferAddress,
+    /// Offset of the next free byte within `buffer`.
+    cursor: BufferAddress,
+}
+
+impl Chunk {
+    fn new(device: &Device, size: BufferAddress) -> Self {
+        let buffer = device.create_buffer(&BufferDescriptor {
+            label: Some("wgpu-mc staging chunk"),
+            size,
+            usage: BufferUsages::MAP_WRITE | BufferUsages::COPY_SRC,
+            mapped_at_creation: true,
+        });
+
+        Self {
+            buffer,
+            size,
+            cursor: 0,
+        }
+    }
+}
+
+/// A single pending upload: `size` bytes starting at `src_offset` within an
+/// active chunk need to be copied into `scene.chunk_buffer` at `dst_offset`
+/// once the frame's writes are done.
+struct PendingCopy {
+    chunk_index: usize,
+    src_offset: BufferAddress,
+    dst_offset: BufferAddress,
+    size: BufferAddress,
+}
+
+/// A pool of mappable staging buffers that batches CPU -> GPU uploads into a
+/// single set of `copy_buffer_to_buffer` commands per frame. Call
+/// [CpuWriteGpuReadBelt::allocate] to write, [CpuWriteGpuReadBelt::before_submit]
+/// before `queue.submit`, and [CpuWriteGpuReadBelt::recall] after.
+pub struct CpuWriteGpuReadBelt {
+    chunk_size: BufferAddress,
+    /// Chunks that are mapped and ready to be written into.
+    free_chunks: Vec<Chunk>,
+    /// Chunks that have had data written into them this frame.
+    active_chunks: Vec<Chunk>,
+    /// Chunks whose `map_async` has completed since they were recalled;
+    /// moved into `free_chunks` on the next [CpuWriteGpuReadBelt::recall].
+    retired_chunks: std::sync::Arc<parking_lot::Mutex<Vec<Chunk>>>,
+    pending_copies: Vec<PendingCopy>,
+}
+
+impl CpuWriteGpuReadBelt {
+    /// Creates a belt that allocates new chunks of at least `chunk_size`
+    /// bytes whenever no free chunk has enough remaining space.
+    pub fn new(chunk_size: BufferAddress) -> Self {
+        Self {
+            chunk_size: chunk_size.max(DEFAULT_CHUNK_SIZE),
+            free_chunks: Vec::new(),
+            active_chunks: Vec::new(),
+            retired_chunks: std::sync::Arc::new(parking_lot::Mutex::new(Vec::new())),
+            pending_copies: Vec::new(),
+        }
+    }
+
+    /// Maps `size` bytes of write-only CPU memory, to be copied to
+    /// `dst_offset` within the destination buffer passed to
+    /// [CpuWriteGpuReadBelt::before_submit]. The returned slice is exactly
+    /// `size` bytes, regardless of `size`'s alignment.
+    pub fn allocate(
+        &mut self,
+        device: &Device,
+        dst_offset: BufferAddress,
+        size: BufferAddress,
+    ) -> &mut [u8] {
+        let aligned_size = align_copy_size(size);
+        let chunk_index = self.find_or_create_chunk(device, aligned_size);
+
+        let chunk = &mut self.active_chunks[chunk_index];
+        let src_offset = chunk.cursor;
+        chunk.cursor += aligned_size;
+
+        self.pending_copies.push(PendingCopy {
+            chunk_index,
+            src_offset,
+            dst_offset,
+            size: aligned_size,
+        });
+
+        let chunk = &mut self.active_chunks[chunk_index];
+        let mut view = chunk
+            .buffer
+            .slice(src_offset..src_offset + aligned_size)
+            .get_mapped_range_mut();
+
+        // SAFETY: borrows `chunk.buffer`, owned by `self` and mapped until
+        // `before_submit` unmaps it. Truncated to `size` (not the padded
+        // `aligned_size`) so `copy_from_slice` can't panic on length.
+        unsafe { std::slice::from_raw_parts_mut(view.as_mut_ptr(), size as usize) }
+    }
+
+    /// Finds a free chunk with enough remaining space for `size` bytes,
+    /// moving it to the active list, or allocates a new one.
+    fn find_or_create_chunk(&mut self, device: &Device, size: BufferAddress) -> usize {
+        let existing = self
+            .free_chunks
+            .iter()
+            .position(|chunk| has_remaining_capacity(chunk.size, chunk.cursor, size));
+
+        if let Some(index) = existing {
+            let chunk = self.free_chunks.swap_remove(index);
+            self.active_chunks.push(chunk);
+            return self.active_chunks.len() - 1;
+        }
+
+        // Also consider chunks already made active this frame.
+        if let Some(chunk) = self
+            .active_chunks
+            .iter()
+            .position(|chunk| has_remaining_capacity(chunk.size, chunk.cursor, size))
+        {
+            return chunk;
+        }
+
+        let chunk_size = size.max(self.chunk_size);
+        self.active_chunks.push(Chunk::new(device, chunk_size));
+        self.active_chunks.len() - 1
+    }
+
+    /// Unmaps every active chunk and records the batched
+    /// `copy_buffer_to_buffer` commands into `encoder`, copying into
+    /// `destination`.
+    pub fn before_submit(&mut self, encoder: &mut CommandEncoder, destination: &Buffer) {
+        for chunk in &self.active_chunks {
+            chunk.buffer.unmap();
+        }
+
+        for copy in self.pending_copies.drain(..) {
+            let chunk = &self.active_chunks[copy.chunk_index];
+            encoder.copy_buffer_to_buffer(
+                &chunk.buffer,
+                copy.src_offset,
+                destination,
+                copy.dst_offset,
+                copy.size,
+            );
+        }
+    }
+
+    /// Schedules every chunk used this frame to be re-mapped and returned to
+    /// the free list once the GPU is done reading from it, and moves chunks
+    /// whose re-mapping has completed since the previous call into the free
+    /// list. Call this after `queue.submit`.
+    pub fn recall(&mut self) {
+        for mut chunk in self.active_chunks.drain(..) {
+            chunk.cursor = 0;
+
+            let chunk = std::sync::Arc::new(chunk);
+            let callback_chunk = chunk.clone();
+            let retired_chunks = self.retired_chunks.clone();
+
+            chunk
+                .buffer
+                .slice(..)
+                .map_async(MapMode::Write, move |result| {
+                    if result.is_ok() {
+                        if let Ok(chunk) = std::sync::Arc::try_unwrap(callback_chunk) {
+                            retired_chunks.lock().push(chunk);
+                        }
+                    }
+                });
+        }
+
+        self.free_chunks.append(&mut self.retired_chunks.lock());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{align_copy_size, has_remaining_capacity};
+
+    #[test]
+    fn align_copy_size_rounds_up_to_copy_buffer_alignment() {
+        let align = wgpu::COPY_BUFFER_ALIGNMENT;
+        assert_eq!(align_copy_size(0), 0);
+        assert_eq!(align_copy_size(1), align);
+        assert_eq!(align_copy_size(align), align);
+        assert_eq!(align_copy_size(align + 1), align * 2);
+    }
+
+    #[test]
+    fn has_remaining_capacity_checks_space_after_cursor() {
+        assert!(has_remaining_capacity(1024, 0, 1024));
+        assert!(has_remaining_capacity(1024, 512, 512));
+        assert!(!has_remaining_capacity(1024, 512, 513));
+        assert!(!has_remaining_capacity(1024, 1024, 1));
+    }
+}